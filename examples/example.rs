@@ -33,14 +33,16 @@ impl eframe::App for ExampleApp {
         egui::Window::new("egui-modal").show(ctx, |ui| {
             // you can put the modal creation and show logic wherever you want
             // (though of course it needs to be created before it can be used)
-            let nested_modal = Modal::new(ctx, "nested_modal");
-            let modal = Modal::new(ctx, "modal")
+            let nested_modal = Modal::new("nested_modal");
+            let modal = Modal::new("modal")
                 .with_style(&self.modal_style)
                 .with_close_on_outside_click(self.close_on_outside_click || !self.include_buttons);
 
             // the show function defines what is shown in the modal, but the modal
-            // won't actually show until you do modal.open(ctx)
-            modal.show(|ui| {
+            // won't actually show until you do modal.open(ctx). the content closure is passed
+            // a `ModalControl` handle you can use to close the modal (or check whether it's
+            // still open) without needing `ctx` yourself
+            modal.show(ctx, |ui, _control| {
                 // these helper functions are NOT mandatory to use, they just
                 // help implement some styling with margins and separators
                 // you can put whatever you like in here
@@ -64,10 +66,12 @@ impl eframe::App for ExampleApp {
                             .suggested_button(ui, "open another modal")
                             .clicked()
                         {
-                            // always close your previous modal before opening a new one otherwise weird
-                            // layering things will happen. again, the helper functions for the buttons automatically
-                            // close the modal on click, so we don't have to manually do that here
-                            nested_modal.open();
+                            // modals layer correctly no matter what order they're opened in -
+                            // the default ModalManager tracks the stack for us, so opening a
+                            // modal from within another "just works". again, the helper
+                            // functions for the buttons automatically close the modal on
+                            // click, so we don't have to manually do that here
+                            nested_modal.open(ctx);
                         }
                     })
                 }
@@ -75,7 +79,7 @@ impl eframe::App for ExampleApp {
 
             ui.with_layout(egui::Layout::top_down_justified(egui::Align::Min), |ui| {
                 if ui.button("open modal").clicked() {
-                    modal.open();
+                    modal.open(ctx);
                 }
                 ui.separator();
                 // to prevent locking the example window without any way to close the modal :)
@@ -142,7 +146,7 @@ impl eframe::App for ExampleApp {
             // why is this down here?? just wanted to show that you can put
             // the modal's [`.show()`] anywhere but we could have put this above
             // modal if we wanted
-            nested_modal.show(|ui| {
+            nested_modal.show(ctx, |ui, _control| {
                 nested_modal.body(ui, "hello there!");
                 nested_modal.buttons(ui, |ui| {
                     nested_modal.button(ui, "close");
@@ -1,14 +1,15 @@
 use eframe::{
     self,
     egui::{
-        style::Margin, Area, Button, Context, Frame, Id, InnerResponse, Label, LayerId, Layout, Order, Response, RichText, Sense, TextEdit, Ui,
-        Window,
+        style::Margin, Area, Button, Context, Frame, Id, InnerResponse, Key, Label, LayerId, Layout, Order, Response, RichText, Sense, TextEdit,
+        Ui, Window,
     },
-    emath::{Align, Align2},
+    emath::{lerp, Align, Align2, TSTransform},
     epaint::{Color32, Pos2, Rounding},
 };
 
 /// The different styles a modal button can take.
+#[derive(Clone, Copy)]
 pub enum ModalButtonStyle {
     /// A normal [`egui`] button
     None,
@@ -18,23 +19,47 @@ pub enum ModalButtonStyle {
     Caution,
 }
 
+/// Controls how a [`Modal`] can be dismissed by the user, modeled on [`egui`]'s
+/// `PopupCloseBehavior`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ModalCloseBehavior {
+    /// The modal only closes via an explicit [`Modal::close`] call (or a helper button).
+    Manual,
+    /// The modal closes when the user clicks outside of it, onto the overlay.
+    CloseOnOutsideClick,
+    /// The modal closes when the user presses the escape key.
+    CloseOnEscape,
+    /// The modal closes when the user clicks outside of it, or presses the escape key.
+    CloseOnOutsideClickOrEscape,
+    /// The modal closes on any click, whether inside or outside of it.
+    CloseOnAnyClick,
+}
+
 #[derive(Clone)]
-/// Information about the current state of the modal. (Pretty empty
-/// right now but may be expanded upon in the future.)
+/// Information about the current state of the modal.
 struct ModalState {
     is_open: bool,
+    /// Whether the modal is still visible this frame. This can briefly be `true` even after
+    /// `is_open` has gone `false`, while its close animation (see [`ModalStyle::animate`])
+    /// is still playing out.
+    visually_present: bool,
+    /// Set by [`Modal::open`] and consumed by the very next [`Modal::show`] call. Suppresses
+    /// [`ModalCloseBehavior::CloseOnAnyClick`] dismissal for that one frame, since the click
+    /// that opened the modal is still the current frame's "any click" and would otherwise
+    /// immediately close it back again.
+    skip_any_click_dismiss: bool,
 }
 
 #[derive(Clone)]
-/// Contains styling parameters for the modal, like body margin 
+/// Contains styling parameters for the modal, like body margin
 /// and button colors.
 pub struct ModalStyle {
-    /// The margin around the modal body. Only applies if using 
+    /// The margin around the modal body. Only applies if using
     /// [`.body()`]
     pub body_margin: f32,
     /// The color of the overlay that dims the background
     pub overlay_color: Color32,
-    
+
     /// The fill color for the caution button style
     pub caution_button_fill: Color32,
     /// The fill color for the suggested button style
@@ -44,6 +69,13 @@ pub struct ModalStyle {
     pub caution_button_text_color: Color32,
     /// The text color for the suggested button style
     pub suggested_button_text_color: Color32,
+
+    /// Whether opening/closing the modal should be animated (the overlay fades in/out and the
+    /// window subtly scales in/out) rather than appearing/disappearing instantly. The window's
+    /// own opacity is not animated.
+    pub animate: bool,
+    /// How long, in seconds, the open/close animation takes when [`ModalStyle::animate`] is set.
+    pub animation_duration: f32,
 }
 
 impl ModalState {
@@ -55,9 +87,107 @@ impl ModalState {
     }
 }
 
+fn set_open_state(ctx: &Context, id: Id, is_open: bool) {
+    let mut modal_state = ModalState::load(ctx, id);
+    modal_state.is_open = is_open;
+    modal_state.save(ctx, id)
+}
+
+/// A handle passed into the modal's content closure, allowing it to close the modal
+/// or query its state without needing the [`Context`] or [`Id`] directly.
+pub struct ModalControl<'a> {
+    ctx: &'a Context,
+    id: Id,
+}
+
+impl<'a> ModalControl<'a> {
+    /// Closes the modal this frame. Goes through the same path as [`Modal::close`], so the
+    /// manager stays consistent regardless of which close API the caller used.
+    pub fn close(&self) {
+        set_open_state(self.ctx, self.id, false);
+    }
+
+    /// Returns whether the modal is currently open.
+    pub fn is_open(&self) -> bool {
+        ModalState::load(self.ctx, self.id).is_open
+    }
+}
+
+/// The result of showing a [`Modal`], returned from [`Modal::show`] while the modal is open
+/// or still playing its close animation.
+pub struct ModalResponse<R> {
+    /// The value returned by the content closure.
+    pub inner: R,
+    /// Whether the overlay behind the modal was clicked this frame.
+    pub backdrop_clicked: bool,
+    /// Whether the modal is still open after this frame.
+    pub is_open: bool,
+}
+
+#[derive(Clone, Default)]
+struct ModalManagerState {
+    stack: Vec<Id>,
+}
+
+impl ModalManagerState {
+    fn load(ctx: &Context, id: Id) -> Self {
+        ctx.data().get_persisted(id).unwrap_or_default()
+    }
+    fn save(self, ctx: &Context, id: Id) {
+        ctx.data().insert_persisted(id, self)
+    }
+}
+
+/// Owns a stack of currently-open modal [`Id`]s so that multiple (and possibly nested) modals
+/// layer correctly: only the topmost modal dims the background and can be dismissed by an
+/// outside click, and a modal opened from within another is automatically rendered above it.
+///
+/// Ordinary single-modal usage doesn't need to think about this at all: every [`Modal`] uses
+/// a lazily-created default global manager unless overridden with [`Modal::with_manager`].
+#[derive(Clone)]
+pub struct ModalManager {
+    id: Id,
+}
+
+impl Default for ModalManager {
+    fn default() -> Self {
+        Self { id: Id::new("egui_modal_global_manager") }
+    }
+}
+
+impl ModalManager {
+    /// Creates a [`ModalManager`] with its own stack, separate from the default global one.
+    /// Useful if you want a set of modals to layer independently of every other modal in the
+    /// application.
+    pub fn new(id_source: impl std::fmt::Display) -> Self {
+        Self { id: Id::new(id_source.to_string()) }
+    }
+
+    fn state(&self, ctx: &Context) -> ModalManagerState {
+        ModalManagerState::load(ctx, self.id)
+    }
+
+    fn push_to_top(&self, ctx: &Context, modal_id: Id) {
+        let mut state = self.state(ctx);
+        state.stack.retain(|&id| id != modal_id);
+        state.stack.push(modal_id);
+        state.save(ctx, self.id);
+    }
+
+    fn remove(&self, ctx: &Context, modal_id: Id) {
+        let mut state = self.state(ctx);
+        state.stack.retain(|&id| id != modal_id);
+        state.save(ctx, self.id);
+    }
+
+    fn is_top(&self, ctx: &Context, modal_id: Id) -> bool {
+        self.state(ctx).stack.last() == Some(&modal_id)
+    }
+}
+
 impl Default for ModalState {
     fn default() -> Self {
-        Self { is_open: false }
+        Self { is_open: false, visually_present: false, skip_any_click_dismiss: false }
     }
 }
 
@@ -72,6 +202,9 @@ impl Default for ModalStyle {
 
             caution_button_text_color: Color32::from_rgb(242, 148, 148),
             suggested_button_text_color: Color32::from_rgb(141, 182, 242),
+
+            animate: false,
+            animation_duration: 0.15,
         }
     }
 }
@@ -79,7 +212,7 @@ impl Default for ModalStyle {
 /// using [`Modal::new()`] to ensure you can call things like [`Modal::open()`] later on.
 /// ```
 /// let modal = Modal::new("my_modal");
-/// modal.show(ctx, |ui| {
+/// modal.show(ctx, |ui, _control| {
 ///     ui.label("Hello world!")
 /// });
 /// if ui.button("modal").clicked() {
@@ -87,7 +220,9 @@ impl Default for ModalStyle {
 /// }
 /// ```
 pub struct Modal {
-    close_on_outside_click: bool,
+    close_behavior: ModalCloseBehavior,
+    dismiss_guard: Option<Box<dyn Fn(&Context) -> bool>>,
+    manager: ModalManager,
     style: ModalStyle,
     id: Id,
     window_id: Id,
@@ -112,36 +247,100 @@ impl Modal {
         Self {
             id: Id::new(id_source.to_string()),
             style: ModalStyle::default(),
-            close_on_outside_click: false,
+            close_behavior: ModalCloseBehavior::Manual,
+            dismiss_guard: None,
+            manager: ModalManager::default(),
             window_id: Id::new("window_".to_string() + &id_source.to_string()),
         }
     }
 
     fn set_open_state(&self, ctx: &Context, is_open: bool) {
-        let mut modal_state = ModalState::load(ctx, self.id);
-        modal_state.is_open = is_open;
-        modal_state.save(ctx, self.id)
+        set_open_state(ctx, self.id, is_open)
     }
 
     /// Open the modal; make it visible. The modal prevents user input to other parts of the
-    /// application.
+    /// application. Pushes this modal onto the top of its [`ModalManager`]'s stack, so it
+    /// (and any modal subsequently opened from within it) layers correctly above whatever
+    /// else is already open.
     pub fn open(&self, ctx: &Context) {
-        self.set_open_state(ctx, true)
+        self.set_open_state(ctx, true);
+        self.manager.push_to_top(ctx, self.id);
+        // The click that opened the modal (if any) is still live in `ctx.input()` this frame,
+        // so skip the very next `CloseOnAnyClick` check to avoid it immediately dismissing the
+        // modal it just opened.
+        let mut modal_state = ModalState::load(ctx, self.id);
+        modal_state.skip_any_click_dismiss = true;
+        modal_state.save(ctx, self.id);
     }
 
     /// Close the modal so that it is no longer visible, allowing input to flow back into
-    /// the application.
+    /// the application. Bypasses [`Modal::with_dismiss_guard`], since this is an explicit,
+    /// programmatic close rather than a user-initiated dismissal.
+    ///
+    /// This only flips the modal's open flag; it stays on its [`ModalManager`]'s stack until
+    /// [`Modal::show`] notices its close animation (if any) has actually finished playing, so a
+    /// modal stacked underneath doesn't prematurely become "top" while this one is still fading
+    /// out.
     pub fn close(&self, ctx: &Context) {
-        self.set_open_state(ctx, false)
+        self.set_open_state(ctx, false);
+    }
+
+    /// Tries to dismiss the modal in response to a user action (an outside click, the escape
+    /// key, or any click, per its [`ModalCloseBehavior`]) rather than an explicit
+    /// [`Modal::close`] call. If a
+    /// [`Modal::with_dismiss_guard`] is set and returns `false`, the dismissal is ignored and
+    /// the modal stays open.
+    fn dismiss(&self, ctx: &Context) {
+        if self.dismiss_guard.as_ref().map_or(true, |guard| guard(ctx)) {
+            self.close(ctx);
+        }
+    }
+
+    /// Sets the [`ModalCloseBehavior`] governing how this modal can be dismissed by the user.
+    pub fn with_close_behavior(mut self, close_behavior: ModalCloseBehavior) -> Self {
+        self.close_behavior = close_behavior;
+        self
     }
 
     /// If set to `true`, the modal will close itself if the user clicks outside on the modal window
-    /// (onto the overlay).
+    /// (onto the overlay). A thin wrapper around [`Modal::with_close_behavior`] kept for
+    /// backwards compatibility.
     pub fn with_close_on_outside_click(mut self, do_close_on_click_ouside: bool) -> Self {
-        self.close_on_outside_click = do_close_on_click_ouside;
+        self.close_behavior = if do_close_on_click_ouside {
+            ModalCloseBehavior::CloseOnOutsideClick
+        } else {
+            ModalCloseBehavior::Manual
+        };
         self
     }
-    
+
+    fn closes_on_outside_click(&self) -> bool {
+        matches!(
+            self.close_behavior,
+            ModalCloseBehavior::CloseOnOutsideClick | ModalCloseBehavior::CloseOnOutsideClickOrEscape | ModalCloseBehavior::CloseOnAnyClick
+        )
+    }
+
+    fn closes_on_escape(&self) -> bool {
+        matches!(self.close_behavior, ModalCloseBehavior::CloseOnEscape | ModalCloseBehavior::CloseOnOutsideClickOrEscape)
+    }
+
+    /// Sets a guard that is consulted whenever the modal is about to be dismissed by the user
+    /// (e.g. an outside click) rather than by an explicit [`Modal::close`] call. Returning
+    /// `false` from the guard keeps the modal open; this is useful for e.g. refusing to close
+    /// a form with unsaved changes.
+    pub fn with_dismiss_guard(mut self, guard: impl Fn(&Context) -> bool + 'static) -> Self {
+        self.dismiss_guard = Some(Box::new(guard));
+        self
+    }
+
+    /// Registers this modal with a specific [`ModalManager`] instead of the default global
+    /// one, so its layering is tracked independently of every other modal in the application.
+    pub fn with_manager(mut self, manager: ModalManager) -> Self {
+        self.manager = manager;
+        self
+    }
+
     /// Change the [`ModalStyle`] of the modal upon creation.
     pub fn with_style(mut self, style: &ModalStyle) -> Self {
         self.style = style.clone();
@@ -209,33 +408,197 @@ impl Modal {
         response
     }
 
-    /// The ui contained in this function will be shown within the modal window. The modal will only actually show 
-    /// when [`Modal::open`] is used. 
-    pub fn show<R>(&self, ctx: &Context, add_contents: impl FnOnce(&mut Ui) -> R) {
+    /// The ui contained in this function will be shown within the modal window. The modal will only actually show
+    /// when [`Modal::open`] is used.
+    ///
+    /// Returns `None` if the modal isn't open (and has finished any close animation), otherwise
+    /// a [`ModalResponse`] carrying whatever `add_contents` returned along with
+    /// `backdrop_clicked` and `is_open` for this frame. The closure is passed a
+    /// [`ModalControl`] handle so it can close the modal (or check whether it's still open)
+    /// without needing the [`Context`] or [`Id`] itself.
+    pub fn show<R>(&self, ctx: &Context, add_contents: impl FnOnce(&mut Ui, &mut ModalControl) -> R) -> Option<ModalResponse<R>> {
         let mut modal_state = ModalState::load(ctx, self.id);
-        if modal_state.is_open {
-            let ctx_clone = ctx.clone();
+        let skip_any_click_dismiss = modal_state.skip_any_click_dismiss;
+        modal_state.skip_any_click_dismiss = false;
+        let t = if self.style.animate {
+            ctx.animate_bool_with_time(self.id.with("anim"), modal_state.is_open, self.style.animation_duration)
+        } else if modal_state.is_open {
+            1.0
+        } else {
+            0.0
+        };
+        modal_state.visually_present = t > 0.0;
+        modal_state.clone().save(ctx, self.id);
+        if !modal_state.visually_present {
+            // Only safe to drop this modal from the manager's stack once it's actually done
+            // rendering (i.e. any close animation has finished); otherwise a modal stacked
+            // underneath would incorrectly become "top" while this one is still fading out.
+            self.manager.remove(ctx, self.id);
+            return None;
+        }
+        let ctx_clone = ctx.clone();
+        let mut backdrop_clicked = false;
+        // Only the topmost modal of the manager's stack dims the background and can be
+        // dismissed by an outside click; lower modals in the stack skip their own overlay so
+        // stacked/nested modals don't double-dim or steal the outside click from the top one.
+        let is_top = self.manager.is_top(ctx, self.id);
+        if is_top {
+            let base_color = self.style.overlay_color;
+            let overlay_color = Color32::from_rgba_unmultiplied(base_color.r(), base_color.g(), base_color.b(), (base_color.a() as f32 * t).round() as u8);
             Area::new(self.id).interactable(true).fixed_pos(Pos2::ZERO).show(ctx, |ui: &mut Ui| {
                 let screen_rect = ui.ctx().input().screen_rect;
                 let area_response = ui.allocate_response(screen_rect.size(), Sense::click());
-                if area_response.clicked() && self.close_on_outside_click {
-                    self.close(ctx);
+                if area_response.clicked() {
+                    backdrop_clicked = true;
+                    if self.closes_on_outside_click() {
+                        self.dismiss(ctx);
+                    }
                 }
-                ui.painter().rect_filled(screen_rect, Rounding::none(), self.style.overlay_color);
+                ui.painter().rect_filled(screen_rect, Rounding::none(), overlay_color);
             });
-            let window = Window::new("")
-                .id(self.window_id)
-                .open(&mut modal_state.is_open)
-                .title_bar(false)
-                .anchor(Align2::CENTER_CENTER, [0., 0.])
-                .resizable(false);
-
-            let response = window.show(&ctx_clone, add_contents);
-            if let Some(inner_response) = response {
-                inner_response.response.request_focus();
+            if self.closes_on_escape() && ctx.input().key_pressed(Key::Escape) {
+                self.dismiss(ctx);
+            }
+            if self.close_behavior == ModalCloseBehavior::CloseOnAnyClick && !skip_any_click_dismiss && ctx.input().pointer.any_click() {
+                self.dismiss(ctx);
+            }
+        }
+        // Gate visibility on `visually_present` (checked above) rather than on `is_open`
+        // directly, so the window keeps rendering while a close animation is still playing.
+        let mut window_visible = true;
+        let window = Window::new("")
+            .id(self.window_id)
+            .open(&mut window_visible)
+            .title_bar(false)
+            .anchor(Align2::CENTER_CENTER, [0., 0.])
+            .resizable(false);
+
+        let mut control = ModalControl { ctx: &ctx_clone, id: self.id };
+        let response = window.show(&ctx_clone, |ui| add_contents(ui, &mut control));
+        response.map(|inner_response| {
+            inner_response.response.request_focus();
+            if is_top {
                 ctx_clone.move_to_top(inner_response.response.layer_id);
             }
+            if self.style.animate {
+                let scale = lerp(0.97..=1.0, t);
+                // Pivot the scale around the window's own center instead of the screen's
+                // origin, so it shrinks/grows in place rather than sliding toward the corner.
+                let center = inner_response.response.rect.center();
+                let pivot_translation = center.to_vec2() * (1.0 - scale);
+                let transform = TSTransform::new(pivot_translation, scale);
+                ctx_clone.transform_layer_shapes(inner_response.response.layer_id, transform);
+            }
+            ModalResponse {
+                inner: inner_response.inner,
+                backdrop_clicked,
+                is_open: ModalState::load(&ctx_clone, self.id).is_open,
+            }
+        })
+    }
+}
+
+/// A single choice shown as a button in a [`Dialog`], pairing its label and [`ModalButtonStyle`]
+/// with the value [`Dialog::show`] returns when it's clicked.
+pub struct DialogButton<T> {
+    /// The text shown on the button.
+    pub text: String,
+    /// The button's style.
+    pub style: ModalButtonStyle,
+    /// The value returned from [`Dialog::show`] when this button is clicked.
+    pub value: T,
+}
+
+impl<T> DialogButton<T> {
+    /// Creates a new [`DialogButton`].
+    pub fn new(text: impl Into<String>, style: ModalButtonStyle, value: T) -> Self {
+        Self { text: text.into(), style, value }
+    }
+}
+
+/// A high-level confirm/alert/prompt dialog built on top of [`Modal`]. Unlike a bare [`Modal`],
+/// a [`Dialog`] already knows its choices, so [`Dialog::show`] returns `Some(value)` the frame
+/// a choice is clicked instead of requiring the caller to wire up its own `if clicked` branches
+/// and close the modal itself.
+pub struct Dialog<T> {
+    modal: Modal,
+    title: String,
+    body: String,
+    choices: Vec<DialogButton<T>>,
+}
+
+impl<T: Clone + Send + Sync + 'static> Dialog<T> {
+    /// Creates a new [`Dialog`] with the given title, body, and choices.
+    pub fn new(id_source: impl std::fmt::Display, title: impl Into<String>, body: impl Into<String>, choices: Vec<DialogButton<T>>) -> Self {
+        Self {
+            modal: Modal::new(id_source),
+            title: title.into(),
+            body: body.into(),
+            choices,
         }
-        // frame.
+    }
+
+    /// Change the [`ModalStyle`] of the dialog's underlying modal upon creation.
+    pub fn with_style(mut self, style: &ModalStyle) -> Self {
+        self.modal = self.modal.with_style(style);
+        self
+    }
+
+    fn selection_id(&self) -> Id {
+        self.modal.id.with("dialog_selection")
+    }
+
+    /// Open the dialog; make it visible.
+    pub fn open(&self, ctx: &Context) {
+        self.modal.open(ctx);
+    }
+
+    /// Close the dialog without a choice having been made.
+    pub fn close(&self, ctx: &Context) {
+        self.modal.close(ctx);
+    }
+
+    /// Shows the dialog if open. Returns `Some(value)` the frame one of its choices is clicked,
+    /// and `None` every other frame (including while the dialog remains closed).
+    pub fn show(&self, ctx: &Context) -> Option<T> {
+        let selection_id = self.selection_id();
+        ctx.data().insert_temp::<Option<T>>(selection_id, None);
+        self.modal.show(ctx, |ui, _control| {
+            self.modal.title(ui, self.title.clone());
+            self.modal.body(ui, self.body.clone());
+            self.modal.buttons(ui, |ui| {
+                for choice in &self.choices {
+                    if self.modal.styled_button(ui, choice.text.clone(), choice.style).clicked() {
+                        ctx.data().insert_temp(selection_id, Some(choice.value.clone()));
+                    }
+                }
+            });
+        });
+        ctx.data().get_temp::<Option<T>>(selection_id).flatten()
+    }
+}
+
+impl Dialog<bool> {
+    /// A confirm dialog with "yes" and "no" choices, returning `true` for "yes".
+    pub fn confirm(id_source: impl std::fmt::Display, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Dialog::new(
+            id_source,
+            title,
+            body,
+            // `buttons()` lays out right-to-left, so the first button pushed ends up
+            // rightmost; push the suggested choice first to match normal dialog convention.
+            vec![DialogButton::new("yes", ModalButtonStyle::Suggested, true), DialogButton::new("no", ModalButtonStyle::None, false)],
+        )
+    }
+
+    /// An "ok"/"cancel" dialog, returning `true` for "ok".
+    pub fn ok_cancel(id_source: impl std::fmt::Display, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Dialog::new(
+            id_source,
+            title,
+            body,
+            // See the comment in `confirm` above about `buttons()`'s right-to-left layout.
+            vec![DialogButton::new("ok", ModalButtonStyle::Suggested, true), DialogButton::new("cancel", ModalButtonStyle::None, false)],
+        )
     }
 }